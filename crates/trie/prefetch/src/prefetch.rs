@@ -1,40 +1,289 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    fs,
+    io,
+    mem::size_of,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+use lru::LruCache;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::{
     sync::{mpsc::UnboundedReceiver, Mutex, oneshot::Receiver},
     task::JoinSet,
+    time::Duration,
 };
 use tracing::{debug, trace};
 
 use reth_db::database::Database;
 use reth_execution_errors::StorageRootError;
-use reth_primitives::{B256, revm_primitives::EvmState};
+#[cfg(feature = "metrics")]
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use reth_primitives::{keccak256, B256, revm_primitives::EvmState};
 use reth_provider::{ProviderError, ProviderFactory, providers::ConsistentDbView};
 use reth_trie::{
     hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory},
+    prefix_set::{PrefixSetMut, TriePrefixSets, TriePrefixSetsMut},
     HashedPostState,
+    HashedPostStateSorted,
     HashedStorage,
     metrics::TrieRootMetrics,
     node_iter::{TrieElement, TrieNodeIter},
     stats::TrieTracker,
-    StorageRoot, trie_cursor::TrieCursorFactory, walker::TrieWalker,
+    Nibbles, StorageRoot, trie_cursor::TrieCursorFactory, walker::TrieWalker,
 };
 use reth_trie_db::{DatabaseHashedCursorFactory, DatabaseTrieCursorFactory};
 use reth_trie_parallel::{parallel_root::ParallelStateRootError, StorageRootTargets};
 
+/// Default memory budget for the combined account/storage prefetch caches, in bytes.
+///
+/// This is a soft limit on the approximate memory used to remember which accounts/slots have
+/// already been warmed, not on the size of the data being prefetched itself.
+const DEFAULT_MAX_CACHE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Approximate size in bytes of a single cached account or storage slot entry: the `CacheKey`
+/// plus the intrusive doubly-linked-list pointers the `lru` crate maintains per entry.
+const CACHE_ENTRY_BYTES: usize = size_of::<CacheKey>() + 2 * size_of::<usize>();
+
+/// Default cap on the number of concurrently in-flight [`TriePrefetch::prefetch_once`] tasks.
+const DEFAULT_MAX_INFLIGHT: usize = 64;
+
+/// Default prefetch channel depth above which we start shedding load by skipping states whose
+/// accounts are already mostly cached.
+const DEFAULT_QUEUE_HIGH_WATERMARK: usize = 256;
+
+/// Fraction of a state's accounts that must already be cached for it to be considered "mostly
+/// warm" and eligible to be dropped under backpressure.
+const MOSTLY_CACHED_THRESHOLD: f64 = 0.8;
+
+/// Current on-disk format version for prefetch cache snapshots. Bump this whenever the
+/// snapshot's shape changes; a mismatched version is treated the same as a missing file.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// How often the warm working set is persisted to the configured snapshot path.
+const SNAPSHOT_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// On-disk representation of the warm working set, used to bootstrap the cache after a restart.
+///
+/// Only the hashed keys are persisted, not the account/storage values themselves, since the
+/// cache only ever tracks "has this already been prefetched". The snapshot is purely advisory:
+/// a stale or corrupt file is discarded silently, because skipping prefetch warm-up is always
+/// safe.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct CacheSnapshot {
+    version: u32,
+    accounts: Vec<B256>,
+    storages: Vec<(B256, B256)>,
+}
+
+impl CacheSnapshot {
+    /// Load and validate a snapshot from `path`, returning `None` if it's missing, corrupt, or
+    /// from an incompatible version.
+    fn load(path: &Path) -> Option<Self> {
+        let bytes = fs::read(path).ok()?;
+        let snapshot: Self = bincode::deserialize(&bytes).ok()?;
+        (snapshot.version == SNAPSHOT_VERSION).then_some(snapshot)
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        let bytes = bincode::serialize(self).map_err(io::Error::other)?;
+        fs::write(path, bytes)
+    }
+}
+
+/// Reconstruct the trie prefix sets and account targets covered by a reloaded snapshot, for a
+/// one-off warm-up prefetch after a restart.
+///
+/// Deliberately does *not* build a `HashedPostState` for these keys: an entry of `None` (account)
+/// or a zero value (storage slot) there is the tombstone sentinel meaning "deleted in this diff",
+/// which makes the hashed cursor skip straight past the entry instead of falling through to the
+/// real on-disk value — exactly backwards for a warm-up whose whole job is to touch the real DB
+/// pages. The prefix sets alone are enough to make the walker visit these paths; the hashed
+/// cursor overlay used alongside them is left empty (see [`TriePrefetch::warm_from_snapshot`]) so
+/// it defers entirely to the database.
+fn snapshot_prefix_sets(snapshot: &CacheSnapshot) -> (Vec<B256>, TriePrefixSetsMut) {
+    let mut account_prefix_set = PrefixSetMut::default();
+    let mut storage_prefix_sets: HashMap<B256, PrefixSetMut> = HashMap::new();
+    let mut account_targets = snapshot.accounts.clone();
+
+    for address in &snapshot.accounts {
+        account_prefix_set.insert(Nibbles::unpack(address));
+    }
+    for (address, slot) in &snapshot.storages {
+        account_prefix_set.insert(Nibbles::unpack(address));
+        storage_prefix_sets.entry(*address).or_default().insert(Nibbles::unpack(slot));
+        account_targets.push(*address);
+    }
+    account_targets.sort_unstable();
+    account_targets.dedup();
+
+    (
+        account_targets,
+        TriePrefixSetsMut {
+            account_prefix_set,
+            storage_prefix_sets,
+            destroyed_accounts: Default::default(),
+        },
+    )
+}
+
+/// Identifies a single entry tracked by [`PrefetchCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Account(B256),
+    Storage(B256, B256),
+}
+
+/// Bounded cache of previously-prefetched accounts and storage slots.
+///
+/// An unbounded cache only ever grows, which is a problem for a long-running node:
+/// every touched address/slot accumulates in memory forever. This tracks an approximate combined
+/// byte budget and evicts least-recently-used entries once it's exceeded, the same way
+/// OpenEthereum's canonical state cache capped its storage cache.
+///
+/// Recency is tracked with an intrusive LRU list (the `lru` crate) rather than a plain `HashMap`,
+/// so a key that's looked up again after being inserted is moved to the front and protected from
+/// eviction; a `HashMap` with insertion-order eviction would evict a constantly-hit hot contract
+/// just as readily as a one-off address, defeating the point of keeping a warm working set.
+#[derive(Debug, Clone)]
+struct PrefetchCache {
+    entries: LruCache<CacheKey, ()>,
+    used_bytes: usize,
+    max_bytes: usize,
+}
+
+impl PrefetchCache {
+    fn new(max_bytes: usize) -> Self {
+        Self { entries: LruCache::unbounded(), used_bytes: 0, max_bytes }
+    }
+
+    fn contains_account(&self, address: &B256) -> bool {
+        self.entries.contains(&CacheKey::Account(*address))
+    }
+
+    fn contains_storage(&self, address: &B256, slot: &B256) -> bool {
+        self.entries.contains(&CacheKey::Storage(*address, *slot))
+    }
+
+    /// Returns whether `address` is cached, marking it as most-recently-used if so.
+    fn touch_account(&mut self, address: &B256) -> bool {
+        self.entries.get(&CacheKey::Account(*address)).is_some()
+    }
+
+    /// Returns whether `(address, slot)` is cached, marking it as most-recently-used if so.
+    fn touch_storage(&mut self, address: &B256, slot: &B256) -> bool {
+        self.entries.get(&CacheKey::Storage(*address, *slot)).is_some()
+    }
+
+    /// Insert an account into the cache, returning the number of entries evicted to stay within
+    /// budget.
+    fn insert_account(&mut self, address: B256) -> u64 {
+        self.insert(CacheKey::Account(address))
+    }
+
+    /// Insert a storage slot into the cache, returning the number of entries evicted to stay
+    /// within budget.
+    fn insert_storage(&mut self, address: B256, slot: B256) -> u64 {
+        self.insert(CacheKey::Storage(address, slot))
+    }
+
+    fn insert(&mut self, key: CacheKey) -> u64 {
+        if self.entries.put(key, ()).is_none() {
+            self.used_bytes += CACHE_ENTRY_BYTES;
+        }
+        self.evict_over_budget()
+    }
+
+    /// Number of distinct cached accounts.
+    fn account_count(&self) -> usize {
+        self.entries.iter().filter(|(key, _)| matches!(key, CacheKey::Account(_))).count()
+    }
+
+    /// All cached account addresses and `(address, slot)` storage pairs, for snapshotting.
+    fn keys(&self) -> (Vec<B256>, Vec<(B256, B256)>) {
+        let mut accounts = Vec::new();
+        let mut storages = Vec::new();
+        for key in self.entries.iter().map(|(key, _)| key) {
+            match key {
+                CacheKey::Account(address) => accounts.push(*address),
+                CacheKey::Storage(address, slot) => storages.push((*address, *slot)),
+            }
+        }
+        (accounts, storages)
+    }
+
+    /// Pop least-recently-used entries until we're back under budget.
+    fn evict_over_budget(&mut self) -> u64 {
+        let mut evicted = 0;
+        while self.used_bytes > self.max_bytes {
+            if self.entries.pop_lru().is_none() {
+                break
+            }
+            self.used_bytes = self.used_bytes.saturating_sub(CACHE_ENTRY_BYTES);
+            evicted += 1;
+        }
+        evicted
+    }
+}
+
+/// Continuous metrics for the prefetch task, complementing the per-call [`TrieRootMetrics`] with
+/// visibility into cache effectiveness and queue depth while the task is running.
+#[cfg(feature = "metrics")]
+#[derive(Metrics, Clone, Debug)]
+#[metrics(scope = "trie.prefetch")]
+struct PrefetchMetrics {
+    /// Number of account/storage lookups that were already cached, so the incoming state was
+    /// deduplicated away.
+    cache_hits: Counter,
+    /// Number of account/storage lookups that were not yet cached, triggering a prefetch.
+    cache_misses: Counter,
+    /// Number of cache entries evicted to stay within the configured memory budget.
+    cache_evictions: Counter,
+    /// Depth of the prefetch channel, sampled once per loop iteration in [`TriePrefetch::run`].
+    queue_depth: Gauge,
+    /// Branches added while prefetching account tries.
+    branches_prefetched: Counter,
+    /// Leaves added while prefetching account tries.
+    leaves_prefetched: Counter,
+    /// Leaves that were not found in the pre-computed storage root map and had to be recomputed
+    /// individually.
+    missed_leaves_prefetched: Counter,
+    /// Branches added while prefetching storage tries, across all accounts.
+    storage_branches_prefetched: Counter,
+    /// Leaves added while prefetching storage tries, across all accounts.
+    storage_leaves_prefetched: Counter,
+    /// Number of states skipped under backpressure.
+    dropped_states: Counter,
+}
+
 /// Prefetch trie storage when executing transactions.
 #[derive(Debug, Clone)]
 pub struct TriePrefetch {
-    /// Cached accounts.
-    cached_accounts: HashMap<B256, bool>,
-    /// Cached storages.
-    cached_storages: HashMap<B256, HashMap<B256, bool>>,
+    /// Bounded, LRU-evicted cache of accounts and storage slots already prefetched.
+    cache: PrefetchCache,
+    /// Maximum number of concurrently in-flight `prefetch_once` tasks.
+    max_inflight: usize,
+    /// Prefetch channel depth above which states that are mostly already cached get skipped
+    /// instead of prefetched.
+    queue_high_watermark: usize,
+    /// Path to periodically persist the warm working set to, and to reload it from on startup.
+    snapshot_path: Option<PathBuf>,
+    /// A snapshot reloaded from `snapshot_path` at construction time, pending an initial warm-up
+    /// prefetch once a provider becomes available in [`Self::run`].
+    pending_warm: Option<CacheSnapshot>,
     global_stats: Arc<Mutex<GlobalStats>>,
     /// State trie metrics.
     #[cfg(feature = "metrics")]
     metrics: TrieRootMetrics,
+    /// Continuous prefetch task metrics.
+    #[cfg(feature = "metrics")]
+    prefetch_metrics: PrefetchMetrics,
 }
 
 #[derive(Default, Debug)]
@@ -42,6 +291,17 @@ pub struct GlobalStats {
     pub branch_prefetched: u64,
     pub leaves_prefetched: u64,
     pub missed_leaves_prefetched: u64,
+    /// Branches added while prefetching storage tries, across all accounts.
+    pub storage_branch_prefetched: u64,
+    /// Leaves added while prefetching storage tries, across all accounts.
+    pub storage_leaves_prefetched: u64,
+    /// Number of cache entries evicted to stay within the configured memory budget. An
+    /// evicted-then-re-seen key is simply treated as a cache miss and re-prefetched, which is
+    /// correct because prefetching is purely a warming optimization.
+    pub evicted: u64,
+    /// Number of states skipped under backpressure because the prefetch channel was backed up
+    /// and the state's accounts were already mostly cached.
+    pub dropped_states: u64,
 }
 
 impl Default for TriePrefetch {
@@ -51,17 +311,83 @@ impl Default for TriePrefetch {
 }
 
 impl TriePrefetch {
-    /// Create new `TriePrefetch` instance.
+    /// Create new `TriePrefetch` instance with the default cache memory budget and backpressure
+    /// limits.
     pub fn new() -> Self {
+        Self::with_limits(DEFAULT_MAX_CACHE_BYTES, DEFAULT_MAX_INFLIGHT, DEFAULT_QUEUE_HIGH_WATERMARK)
+    }
+
+    /// Create a new `TriePrefetch` instance with a custom memory budget (in bytes) for the
+    /// combined account/storage prefetch caches.
+    pub fn with_max_cache_bytes(max_cache_bytes: usize) -> Self {
+        Self::with_limits(max_cache_bytes, DEFAULT_MAX_INFLIGHT, DEFAULT_QUEUE_HIGH_WATERMARK)
+    }
+
+    /// Create a new `TriePrefetch` instance with a custom cache memory budget (in bytes), cap on
+    /// concurrently in-flight prefetch tasks, and prefetch-channel high-water mark above which
+    /// mostly-warm states are skipped instead of prefetched.
+    pub fn with_limits(max_cache_bytes: usize, max_inflight: usize, queue_high_watermark: usize) -> Self {
         Self {
-            cached_accounts: HashMap::new(),
-            cached_storages: HashMap::new(),
+            cache: PrefetchCache::new(max_cache_bytes),
+            max_inflight,
+            queue_high_watermark,
+            snapshot_path: None,
+            pending_warm: None,
             #[cfg(feature = "metrics")]
             metrics: TrieRootMetrics::default(),
+            #[cfg(feature = "metrics")]
+            prefetch_metrics: PrefetchMetrics::default(),
             global_stats: Arc::new(Mutex::new(GlobalStats::default())),
         }
     }
 
+    /// Enable periodically persisting the warm working set to `path`, and reload it from there
+    /// now if a valid snapshot already exists.
+    ///
+    /// A missing, stale, or corrupt snapshot is discarded silently, since prefetching is always
+    /// safe to skip. The cache itself starts empty either way: the reloaded keys only drive a
+    /// one-shot warm-up prefetch in [`Self::run`] (see [`Self::pending_warm`]), rather than being
+    /// marked cached outright, so real traffic touching those keys afterwards is still
+    /// deduplicated and prefetched normally instead of being mistaken for already-warm.
+    pub fn with_snapshot_path(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.pending_warm = CacheSnapshot::load(&path);
+        self.snapshot_path = Some(path);
+        self
+    }
+
+    /// Persist the current warm working set to the configured snapshot path, if any.
+    ///
+    /// Best-effort: failures are logged and otherwise ignored, since snapshotting is purely an
+    /// optimization. The cache is cloned on the calling task; collecting its keys
+    /// (`PrefetchCache::keys`, an O(n) walk that allocates two fresh `Vec`s), serializing, and
+    /// writing are offloaded to the blocking thread pool so they don't stall the async executor
+    /// running [`Self::run`].
+    async fn save_snapshot(&self) {
+        let Some(path) = self.snapshot_path.clone() else { return };
+        let cache = self.cache.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let (accounts, storages) = cache.keys();
+            let snapshot = CacheSnapshot { version: SNAPSHOT_VERSION, accounts, storages };
+            snapshot.save(&path)
+        })
+        .await;
+        if let Err(e) = result.map_err(io::Error::other).and_then(|r| r) {
+            debug!(target: "trie::trie_prefetch", ?e, "Failed to persist trie prefetch cache snapshot");
+        }
+    }
+
+    /// Returns `true` if a large enough fraction of `state`'s accounts are already cached that
+    /// prefetching it again would mostly be wasted work.
+    fn mostly_cached(&self, state: &EvmState) -> bool {
+        if state.is_empty() {
+            return true
+        }
+        let cached =
+            state.keys().filter(|address| self.cache.contains_account(&keccak256(*address))).count();
+        (cached as f64 / state.len() as f64) >= MOSTLY_CACHED_THRESHOLD
+    }
+
     /// Run the prefetching task.
     pub async fn run<DB>(
         &mut self,
@@ -73,12 +399,52 @@ impl TriePrefetch {
     {
         let mut join_set = JoinSet::new();
 
+        // Warm the cache from a reloaded snapshot, if any, now that a provider is available.
+        if let Some(snapshot) = self.pending_warm.take() {
+            let self_clone = Arc::new(self.clone());
+            let global_stats = Arc::clone(&self.global_stats);
+            let consistent_view = Arc::clone(&consistent_view);
+            join_set.spawn(async move {
+                if let Err(e) =
+                    self_clone.warm_from_snapshot::<DB>(consistent_view, snapshot, global_stats).await
+                {
+                    debug!(target: "trie::trie_prefetch", ?e, "Error while warming trie prefetch cache from snapshot");
+                };
+            });
+        }
+
+        let mut snapshot_interval =
+            self.snapshot_path.is_some().then(|| tokio::time::interval(SNAPSHOT_SAVE_INTERVAL));
+
         loop {
+            #[cfg(feature = "metrics")]
+            self.prefetch_metrics.queue_depth.set(prefetch_rx.len() as f64);
+
             tokio::select! {
-                state = prefetch_rx.recv() => {
+                // Shed load gracefully: once too many tasks are in flight, wait for one to finish
+                // before accepting more work, rather than letting the join set grow unbounded.
+                // Kept as its own branch rather than a blocking wait before `select!` so that a
+                // slow in-flight batch of `prefetch_once` calls can't starve `interrupt_rx` and
+                // delay shutdown.
+                _ = join_set.join_next(), if join_set.len() >= self.max_inflight => {}
+                state = prefetch_rx.recv(), if join_set.len() < self.max_inflight => {
                     if let Some(state) = state {
+                        // Under backpressure, skip states that are already mostly warm instead of
+                        // falling further behind.
+                        if prefetch_rx.len() > self.queue_high_watermark && self.mostly_cached(&state) {
+                            self.global_stats.lock().await.dropped_states += 1;
+                            #[cfg(feature = "metrics")]
+                            self.prefetch_metrics.dropped_states.increment(1);
+                            continue
+                        }
+
                         let consistent_view = Arc::clone(&consistent_view);
-                        let hashed_state = self.deduplicate_and_update_cached(state);
+                        let (hashed_state, evicted) = self.deduplicate_and_update_cached(state);
+                        if evicted > 0 {
+                            self.global_stats.lock().await.evicted += evicted;
+                            #[cfg(feature = "metrics")]
+                            self.prefetch_metrics.cache_evictions.increment(evicted);
+                        }
 
                         let self_clone = Arc::new(self.clone());
                         let global_stats = Arc::clone(&self.global_stats);
@@ -89,9 +455,13 @@ impl TriePrefetch {
                         });
                     }
                 }
+                _ = async { snapshot_interval.as_mut().unwrap().tick().await }, if snapshot_interval.is_some() => {
+                    self.save_snapshot().await;
+                }
                 _ = &mut interrupt_rx => {
-                    debug!(target: "trie::trie_prefetch", "Interrupted trie prefetch task. Unprocessed tx {:?}, Processed accounts: {:?}", prefetch_rx.len(), self.cached_accounts.len());
+                    debug!(target: "trie::trie_prefetch", "Interrupted trie prefetch task. Unprocessed tx {:?}, Processed accounts: {:?}", prefetch_rx.len(), self.cache.account_count());
                     join_set.abort_all();
+                    self.save_snapshot().await;
                     debug!(target: "trie::trie_prefetch", "test info: prefetch account trie node count: {:?}", self.global_stats.lock().await);
                     return
                 }
@@ -99,55 +469,69 @@ impl TriePrefetch {
         }
     }
 
-    /// Deduplicate `hashed_state` based on `cached` and update `cached`.
-    fn deduplicate_and_update_cached(&mut self, state: EvmState) -> HashedPostState {
+    /// Deduplicate the hashed `state` against `self.cache` and update the cache, returning the
+    /// deduplicated state and the number of cache entries evicted to make room for the newly
+    /// cached keys.
+    fn deduplicate_and_update_cached(&mut self, state: EvmState) -> (HashedPostState, u64) {
         let hashed_state = HashedPostState::from_state(state);
         let mut new_hashed_state = HashedPostState::default();
+        let mut evicted = 0;
+        #[cfg(feature = "metrics")]
+        let (mut hits, mut misses) = (0u64, 0u64);
 
         // deduplicate accounts if their keys are not present in storages
         for (address, account) in &hashed_state.accounts {
             // if !hashed_state.storages.contains_key(address) &&
-            //     !self.cached_accounts.contains_key(address)
-            if !self.cached_accounts.contains_key(address)
+            //     !self.cache.touch_account(address)
+            if !self.cache.touch_account(address)
             {
-                self.cached_accounts.insert(*address, true);
+                evicted += self.cache.insert_account(*address);
                 new_hashed_state.accounts.insert(*address, *account);
+                #[cfg(feature = "metrics")]
+                { misses += 1; }
+            } else {
+                #[cfg(feature = "metrics")]
+                { hits += 1; }
             }
         }
 
         // deduplicate storages
-        // for (address, storage) in &hashed_state.storages {
-        //     let cached_entry = self.cached_storages.entry(*address).or_default();
-        // 
-        //     // Collect the keys to be added to `new_storage` after filtering
-        //     let keys_to_add: Vec<_> = storage
-        //         .storage
-        //         .iter()
-        //         .filter(|(slot, _)| !cached_entry.contains_key(*slot))
-        //         .map(|(slot, _)| *slot)
-        //         .collect();
-        // 
-        //     // Iterate over `keys_to_add` to update `cached_entry` and `new_storage`
-        //     let new_storage: HashMap<_, _> = keys_to_add
-        //         .into_iter()
-        //         .map(|slot| {
-        //             cached_entry.insert(slot, true);
-        //             (slot, *storage.storage.get(&slot).unwrap())
-        //         })
-        //         .collect();
-        // 
-        //     if !new_storage.is_empty() {
-        //         new_hashed_state
-        //             .storages
-        //             .insert(*address, HashedStorage::from_iter(false, new_storage.into_iter()));
-        // 
-        //         if let Some(account) = hashed_state.accounts.get(address) {
-        //             new_hashed_state.accounts.insert(*address, *account);
-        //         }
-        //     }
-        // }
-
-        new_hashed_state
+        for (address, storage) in &hashed_state.storages {
+            // Collect the slots not yet present in the cache, marking them as cached along the
+            // way.
+            let new_storage: HashMap<_, _> = storage
+                .storage
+                .iter()
+                .filter(|(slot, _)| {
+                    let is_new = !self.cache.touch_storage(address, slot);
+                    #[cfg(feature = "metrics")]
+                    if is_new { misses += 1 } else { hits += 1 };
+                    is_new
+                })
+                .map(|(slot, value)| {
+                    evicted += self.cache.insert_storage(*address, *slot);
+                    (*slot, *value)
+                })
+                .collect();
+
+            if !new_storage.is_empty() {
+                new_hashed_state
+                    .storages
+                    .insert(*address, HashedStorage::from_iter(false, new_storage.into_iter()));
+
+                if let Some(account) = hashed_state.accounts.get(address) {
+                    new_hashed_state.accounts.insert(*address, *account);
+                }
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            self.prefetch_metrics.cache_hits.increment(hits);
+            self.prefetch_metrics.cache_misses.increment(misses);
+        }
+
+        (new_hashed_state, evicted)
     }
 
     /// Prefetch trie storage for the given hashed state.
@@ -157,40 +541,101 @@ impl TriePrefetch {
         hashed_state: HashedPostState,
         global_stats: Arc<Mutex<GlobalStats>>,
     ) -> Result<(), TriePrefetchError>
+    where
+        DB: Database,
+    {
+        let prefix_sets = hashed_state.construct_prefix_sets().freeze();
+        let account_targets: Vec<B256> = hashed_state.accounts.keys().copied().collect();
+        let hashed_state_sorted = hashed_state.into_sorted();
+
+        self.prefetch_with_prefix_sets(
+            consistent_view,
+            prefix_sets,
+            account_targets,
+            hashed_state_sorted,
+            global_stats,
+        )
+        .await
+    }
+
+    /// Warm the cache from a reloaded [`CacheSnapshot`], re-prefetching the trie storage and
+    /// account branches covering its keys.
+    ///
+    /// Deliberately walks with an *empty* hashed state overlay rather than one built from the
+    /// snapshot: restoring `None` accounts or zero-value storage slots into a `HashedPostState`
+    /// would mark them as deleted (see [`snapshot_prefix_sets`]), so the prefix sets alone are
+    /// used to steer the walker onto the right paths while the actual values are read through
+    /// from the database.
+    async fn warm_from_snapshot<DB>(
+        self: Arc<Self>,
+        consistent_view: Arc<ConsistentDbView<DB, ProviderFactory<DB>>>,
+        snapshot: CacheSnapshot,
+        global_stats: Arc<Mutex<GlobalStats>>,
+    ) -> Result<(), TriePrefetchError>
+    where
+        DB: Database,
+    {
+        let (account_targets, prefix_sets) = snapshot_prefix_sets(&snapshot);
+        let prefix_sets = prefix_sets.freeze();
+        let hashed_state_sorted = HashedPostState::default().into_sorted();
+
+        self.prefetch_with_prefix_sets(
+            consistent_view,
+            prefix_sets,
+            account_targets,
+            hashed_state_sorted,
+            global_stats,
+        )
+        .await
+    }
+
+    /// Walk the account and storage tries covering `prefix_sets`, prefetching every branch and
+    /// leaf node onto the hot path. Shared by [`Self::prefetch_once`] and
+    /// [`Self::warm_from_snapshot`], which differ only in how they derive `prefix_sets`,
+    /// `account_targets`, and the hashed-state overlay used to resolve values.
+    async fn prefetch_with_prefix_sets<DB>(
+        self: Arc<Self>,
+        consistent_view: Arc<ConsistentDbView<DB, ProviderFactory<DB>>>,
+        prefix_sets: TriePrefixSets,
+        account_targets: Vec<B256>,
+        hashed_state_sorted: HashedPostStateSorted,
+        global_stats: Arc<Mutex<GlobalStats>>,
+    ) -> Result<(), TriePrefetchError>
     where
         DB: Database,
     {
         let mut tracker = TrieTracker::default();
         let mut leaves_missed = 0u64;
+        let mut storage_branches_prefetched = 0u64;
+        let mut storage_leaves_prefetched = 0u64;
 
-        let prefix_sets = hashed_state.construct_prefix_sets().freeze();
-        let storage_root_targets = StorageRootTargets::new(
-            hashed_state.accounts.keys().copied(),
-            prefix_sets.storage_prefix_sets,
-        );
-        let hashed_state_sorted = hashed_state.into_sorted();
+        let storage_root_targets =
+            StorageRootTargets::new(account_targets.into_iter(), prefix_sets.storage_prefix_sets);
 
         trace!(target: "trie::trie_prefetch", "start prefetching trie storages");
         let mut storage_roots = storage_root_targets
             .into_par_iter()
             .map(|(hashed_address, prefix_set)| {
-                // let provider_ro = consistent_view.provider_ro()?;
-                // let trie_cursor_factory = DatabaseTrieCursorFactory::new(provider_ro.tx_ref());
-                // let hashed_cursor_factory = HashedPostStateCursorFactory::new(
-                //     DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
-                //     &hashed_state_sorted,
-                // );
-                // let storage_root_result = StorageRoot::new_hashed(
-                //     trie_cursor_factory,
-                //     hashed_cursor_factory,
-                //     hashed_address,
-                //     #[cfg(feature = "metrics")]
-                //     self.metrics.clone(),
-                // )
-                // .with_prefix_set(prefix_set)
-                // .prefetch();
-
-                Ok((hashed_address, 1))
+                let provider_ro = consistent_view.provider_ro()?;
+                let trie_cursor_factory = DatabaseTrieCursorFactory::new(provider_ro.tx_ref());
+                let hashed_cursor_factory = HashedPostStateCursorFactory::new(
+                    DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
+                    &hashed_state_sorted,
+                );
+                // A failure to prefetch a single storage trie is not fatal, since prefetching is
+                // purely a warming optimization; fall back to an empty (zero) stats entry.
+                let storage_stats = StorageRoot::new_hashed(
+                    trie_cursor_factory,
+                    hashed_cursor_factory,
+                    hashed_address,
+                    #[cfg(feature = "metrics")]
+                    self.metrics.clone(),
+                )
+                .with_prefix_set(prefix_set)
+                .prefetch()
+                .unwrap_or_default();
+
+                Ok((hashed_address, storage_stats))
             })
             .collect::<Result<HashMap<_, _>, ParallelStateRootError>>()?;
 
@@ -219,7 +664,7 @@ impl TriePrefetch {
                     tracker.inc_branch();
                 }
                 TrieElement::Leaf(hashed_address, _) => {
-                    match storage_roots.remove(&hashed_address) {
+                    let storage_stats = match storage_roots.remove(&hashed_address) {
                         Some(result) => {
                             result
                         }
@@ -240,6 +685,8 @@ impl TriePrefetch {
                             .unwrap_or_default()
                         }
                     };
+                    storage_branches_prefetched += storage_stats.branches_added();
+                    storage_leaves_prefetched += storage_stats.leaves_added();
                     tracker.inc_leaf();
                 }
             }
@@ -250,10 +697,23 @@ impl TriePrefetch {
         #[cfg(feature = "metrics")]
         self.metrics.record(stats);
 
+        #[cfg(feature = "metrics")]
+        {
+            self.prefetch_metrics.branches_prefetched.increment(stats.branches_added());
+            self.prefetch_metrics
+                .leaves_prefetched
+                .increment(stats.leaves_added() - leaves_missed);
+            self.prefetch_metrics.missed_leaves_prefetched.increment(leaves_missed);
+            self.prefetch_metrics.storage_branches_prefetched.increment(storage_branches_prefetched);
+            self.prefetch_metrics.storage_leaves_prefetched.increment(storage_leaves_prefetched);
+        }
+
         let mut gstats = global_stats.lock().await;
         gstats.branch_prefetched += stats.branches_added();
         gstats.leaves_prefetched += stats.leaves_added() - leaves_missed;
         gstats.missed_leaves_prefetched += leaves_missed;
+        gstats.storage_branch_prefetched += storage_branches_prefetched;
+        gstats.storage_leaves_prefetched += storage_leaves_prefetched;
 
         debug!(
             target: "trie::trie_prefetch",
@@ -261,6 +721,8 @@ impl TriePrefetch {
             branches_added = stats.branches_added(),
             leaves_added = stats.leaves_added()-leaves_missed,
             leaves_missed = leaves_missed,
+            storage_branches_added = storage_branches_prefetched,
+            storage_leaves_added = storage_leaves_prefetched,
             "prefetched account trie"
         );
 
@@ -292,4 +754,104 @@ impl From<TriePrefetchError> for ProviderError {
             TriePrefetchError::ParallelStateRoot(error) => error.into(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Address;
+
+    #[test]
+    fn prefetch_cache_evicts_least_recently_used() {
+        let mut cache = PrefetchCache::new(3 * CACHE_ENTRY_BYTES);
+        let addresses: Vec<B256> = (0..4).map(|i| B256::with_last_byte(i)).collect();
+
+        cache.insert_account(addresses[0]);
+        cache.insert_account(addresses[1]);
+        cache.insert_account(addresses[2]);
+
+        // Touch the oldest entry so it becomes most-recently-used, protecting it from eviction.
+        assert!(cache.touch_account(&addresses[0]));
+
+        // Inserting a fourth entry pushes the cache over budget; the least-recently-used entry
+        // (addresses[1], never touched again after its insert) should be evicted, not the one we
+        // just touched.
+        let evicted = cache.insert_account(addresses[3]);
+        assert_eq!(evicted, 1);
+        assert!(cache.contains_account(&addresses[0]));
+        assert!(!cache.contains_account(&addresses[1]));
+        assert!(cache.contains_account(&addresses[2]));
+        assert!(cache.contains_account(&addresses[3]));
+    }
+
+    #[test]
+    fn mostly_cached_respects_threshold() {
+        use reth_primitives::revm_primitives::Account;
+
+        let addresses: Vec<Address> = (0..5).map(|i| Address::with_last_byte(i)).collect();
+        let state: EvmState =
+            addresses.iter().map(|address| (*address, Account::default())).collect();
+
+        let mut prefetch = TriePrefetch::new();
+
+        // 4 out of 5 addresses cached (80%) meets the threshold.
+        for address in &addresses[..4] {
+            prefetch.cache.insert_account(keccak256(address));
+        }
+        assert!(prefetch.mostly_cached(&state));
+
+        // Reset the cache to hold only 3 out of 5 (60%), below the threshold.
+        prefetch.cache = PrefetchCache::new(DEFAULT_MAX_CACHE_BYTES);
+        for address in &addresses[..3] {
+            prefetch.cache.insert_account(keccak256(address));
+        }
+        assert!(!prefetch.mostly_cached(&state));
+    }
+
+    #[test]
+    fn cache_snapshot_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prefetch.snapshot");
+
+        let snapshot = CacheSnapshot {
+            version: SNAPSHOT_VERSION,
+            accounts: vec![B256::with_last_byte(1), B256::with_last_byte(2)],
+            storages: vec![(B256::with_last_byte(3), B256::with_last_byte(4))],
+        };
+        snapshot.save(&path).unwrap();
+
+        let loaded = CacheSnapshot::load(&path).unwrap();
+        assert_eq!(loaded.accounts, snapshot.accounts);
+        assert_eq!(loaded.storages, snapshot.storages);
+    }
+
+    #[test]
+    fn cache_snapshot_load_rejects_mismatched_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prefetch.snapshot");
+
+        let snapshot =
+            CacheSnapshot { version: SNAPSHOT_VERSION + 1, accounts: vec![], storages: vec![] };
+        snapshot.save(&path).unwrap();
+
+        assert!(CacheSnapshot::load(&path).is_none());
+    }
+
+    #[test]
+    fn with_snapshot_path_starts_with_an_empty_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prefetch.snapshot");
+        let address = B256::with_last_byte(1);
+
+        let snapshot = CacheSnapshot { version: SNAPSHOT_VERSION, accounts: vec![address], storages: vec![] };
+        snapshot.save(&path).unwrap();
+
+        let prefetch = TriePrefetch::new().with_snapshot_path(path);
+
+        // The reloaded key must only drive a one-shot warm-up prefetch via `pending_warm`, not be
+        // pre-marked as cached - otherwise real traffic touching it later would be silently
+        // treated as a cache hit and never actually prefetched.
+        assert!(!prefetch.cache.contains_account(&address));
+        assert!(prefetch.pending_warm.is_some());
+    }
+}